@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(start)]
@@ -90,6 +92,330 @@ pub fn pattern_row_u16(guess: &str, secrets: js_sys::Array) -> js_sys::Uint16Arr
     arr
 }
 
+#[inline]
+fn base3_code_u64(trits: &[u8]) -> u64 {
+    // Automatic narrow/wide dispatch: reuse the zero-cost u16 path for the common
+    // L<=10 case, and widen to a u128 accumulator for L up to 40 (3^40 < 2^64, so the
+    // final code still fits in a u64; 3^41 would overflow).
+    let l = trits.len();
+    if l <= 10 {
+        base3_code_u16(trits) as u64
+    } else {
+        let mut code: u128 = 0;
+        let mut mul: u128 = 1;
+        for &t in trits {
+            code += (t as u128) * mul;
+            mul *= 3;
+        }
+        code as u64
+    }
+}
+
+#[wasm_bindgen]
+pub fn feedback_code_u64(guess: &str, secret: &str) -> u64 {
+    let g = guess.as_bytes();
+    let s = secret.as_bytes();
+    assert!(g.len() == s.len(), "length mismatch");
+    assert!(g.len() <= 40, "feedback_code_u64 supports L<=40");
+    let trits = feedback_trits_core(g, s);
+    base3_code_u64(&trits[..g.len()])
+}
+
+#[wasm_bindgen]
+pub fn pattern_row_u64(guess: &str, secrets: js_sys::Array) -> js_sys::BigUint64Array {
+    let g = guess.as_bytes();
+    let l = g.len();
+    assert!(l > 0, "empty guess");
+    assert!(l <= 40, "pattern_row_u64 supports L<=40");
+    let n = secrets.length() as usize;
+    let mut out: Vec<u64> = vec![0u64; n];
+
+    for (i, v) in secrets.iter().enumerate() {
+        let s = v.as_string().expect("secret must be string");
+        let sb = s.as_bytes();
+        assert!(sb.len() == l, "secret length mismatch");
+        let trits = feedback_trits_core(g, sb);
+        out[i] = base3_code_u64(&trits[..l]);
+    }
+    let arr = js_sys::BigUint64Array::new_with_length(n as u32);
+    arr.copy_from(&out);
+    arr
+}
+
+// Shared helper: collect a JS array of secret strings into owned byte buffers once,
+// so repeated entropy scoring doesn't re-walk the JS boundary per guess.
+fn collect_secrets(secrets: &js_sys::Array) -> Vec<Vec<u8>> {
+    secrets
+        .iter()
+        .map(|v| v.as_string().expect("secret must be string").into_bytes())
+        .collect()
+}
+
+// Flat row-major `guesses.len() * secrets.len()` pattern grid plus its row stride
+// (`secrets.len()`), letting JS slice rows out of one dense buffer instead of
+// marshalling a `Uint16Array` per `pattern_row_u16` call.
+#[wasm_bindgen]
+pub struct PatternMatrix {
+    data: js_sys::Uint16Array,
+    stride: u32,
+}
+
+#[wasm_bindgen]
+impl PatternMatrix {
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> js_sys::Uint16Array {
+        self.data.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn stride(&self) -> u32 {
+        self.stride
+    }
+}
+
+// Packs the full `guesses x secrets` pattern grid row-major into a flat buffer, one
+// row per guess. Kept as a plain helper (no JS types) so the indexing is directly
+// testable, the same way `bucket_weights`/`entropy_from_buckets` are.
+fn pack_pattern_matrix(guesses: &[Vec<u8>], secrets: &[Vec<u8>]) -> Vec<u16> {
+    let num_secrets = secrets.len();
+    let mut out: Vec<u16> = vec![0u16; guesses.len() * num_secrets];
+
+    for (gi, g) in guesses.iter().enumerate() {
+        let l = g.len();
+        assert!(l > 0, "empty guess");
+        assert!(l <= 10, "pattern_matrix supports L<=10");
+        let row_start = gi * num_secrets;
+        for (si, s) in secrets.iter().enumerate() {
+            assert!(s.len() == l, "secret length mismatch");
+            let trits = feedback_trits_core(g, s);
+            out[row_start + si] = base3_code_u16(&trits[..l]);
+        }
+    }
+    out
+}
+
+#[wasm_bindgen]
+pub fn pattern_matrix(guesses: js_sys::Array, secrets: js_sys::Array) -> PatternMatrix {
+    // `collect_secrets` just turns a JS string array into owned byte buffers; reuse it
+    // for the guess list too, so both sides are parsed once, up front.
+    let guess_bytes = collect_secrets(&guesses);
+    let secret_bytes = collect_secrets(&secrets);
+    let stride = secret_bytes.len();
+    let out = pack_pattern_matrix(&guess_bytes, &secret_bytes);
+
+    let data = js_sys::Uint16Array::new_with_length(out.len() as u32);
+    data.copy_from(&out);
+    PatternMatrix { data, stride: stride as u32 }
+}
+
+// Buckets `secrets` by the feedback code `guess` induces (same base-3 code as
+// `feedback_code`/`pattern_row_u16`), accumulating either plain counts or, when
+// `weights` is given, summed per-secret weight per bucket. Returns the bucket
+// weights, their total, and whether `guess` is itself one of the possible secrets.
+fn bucket_weights(guess: &[u8], secrets: &[Vec<u8>], weights: Option<&[f64]>) -> (Vec<f64>, f64, bool) {
+    let l = guess.len();
+    assert!(l <= 10, "weighted ranking supports L<=10");
+    if let Some(ws) = weights {
+        assert!(ws.len() == secrets.len(), "weights length mismatch");
+    }
+    let num_buckets = 3usize.pow(l as u32);
+    let mut buckets = vec![0f64; num_buckets];
+    let mut is_possible_secret = false;
+    let mut total = 0.0;
+
+    for (i, s) in secrets.iter().enumerate() {
+        assert!(s.len() == l, "secret length mismatch");
+        if s.as_slice() == guess {
+            is_possible_secret = true;
+        }
+        let w = weights.map_or(1.0, |ws| ws[i]);
+        let trits = feedback_trits_core(guess, s);
+        let code = base3_code_u16(&trits[..l]) as usize;
+        buckets[code] += w;
+        total += w;
+    }
+    (buckets, total, is_possible_secret)
+}
+
+// Shannon entropy (in bits) of a bucket-weight distribution: H = -Σ pᵢ·log2(pᵢ)
+// where pᵢ = bucketᵢ / total. Uniform counts are just weight-1-per-secret.
+fn entropy_from_buckets(buckets: &[f64], total: f64) -> f64 {
+    if total <= 0.0 {
+        return 0.0;
+    }
+    let mut h = 0.0;
+    for &w in buckets {
+        if w == 0.0 {
+            continue;
+        }
+        let p = w / total;
+        h -= p * p.log2();
+    }
+    h
+}
+
+// Expected remaining candidate weight mass after this guess: Σ pᵢ·bucketᵢ, the
+// weighted-mass analogue of entropy for callers that want to minimize leftover
+// candidates directly instead of maximizing information.
+fn expected_remaining_from_buckets(buckets: &[f64], total: f64) -> f64 {
+    if total <= 0.0 {
+        return 0.0;
+    }
+    let mut sum = 0.0;
+    for &w in buckets {
+        if w == 0.0 {
+            continue;
+        }
+        let p = w / total;
+        sum += p * w;
+    }
+    sum
+}
+
+fn guess_entropy(guess: &[u8], secrets: &[Vec<u8>], weights: Option<&[f64]>) -> (f64, bool) {
+    let (buckets, total, is_possible_secret) = bucket_weights(guess, secrets, weights);
+    (entropy_from_buckets(&buckets, total), is_possible_secret)
+}
+
+// Nudge entropy scores so that exact ties (common with small secret sets) resolve in
+// favor of a guess that is itself a possible secret, without disturbing real ordering.
+const TIE_BREAK_EPSILON: f64 = 1e-9;
+
+#[wasm_bindgen]
+pub fn expected_info(guess: &str, secrets: js_sys::Array, weights: Option<js_sys::Float64Array>) -> f64 {
+    let g = guess.as_bytes();
+    assert!(!g.is_empty(), "empty guess");
+    let secret_bytes = collect_secrets(&secrets);
+    let weight_vec = weights.map(|w| w.to_vec());
+    let (h, is_possible_secret) = guess_entropy(g, &secret_bytes, weight_vec.as_deref());
+    if is_possible_secret {
+        h + TIE_BREAK_EPSILON
+    } else {
+        h
+    }
+}
+
+// Scores every candidate guess by expected information gain, one score per input
+// guess in the same order as `guesses` (not sorted) — the tie-break bonus above
+// means a caller can derive the descending-H ranking with a plain sort-by-score,
+// so we leave that ordering step to the JS side rather than reshuffling here.
+#[wasm_bindgen]
+pub fn rank_guesses(
+    guesses: js_sys::Array,
+    secrets: js_sys::Array,
+    weights: Option<js_sys::Float64Array>,
+) -> js_sys::Float64Array {
+    let secret_bytes = collect_secrets(&secrets);
+    let weight_vec = weights.map(|w| w.to_vec());
+    let n = guesses.length() as usize;
+    let mut out: Vec<f64> = Vec::with_capacity(n);
+
+    for v in guesses.iter() {
+        let g = v.as_string().expect("guess must be string");
+        let (h, is_possible_secret) = guess_entropy(g.as_bytes(), &secret_bytes, weight_vec.as_deref());
+        out.push(if is_possible_secret { h + TIE_BREAK_EPSILON } else { h });
+    }
+
+    let arr = js_sys::Float64Array::new_with_length(n as u32);
+    arr.copy_from(&out);
+    arr
+}
+
+#[wasm_bindgen]
+pub fn expected_remaining(
+    guess: &str,
+    secrets: js_sys::Array,
+    weights: Option<js_sys::Float64Array>,
+) -> f64 {
+    let g = guess.as_bytes();
+    assert!(!g.is_empty(), "empty guess");
+    let secret_bytes = collect_secrets(&secrets);
+    let weight_vec = weights.map(|w| w.to_vec());
+    let (buckets, total, _) = bucket_weights(g, &secret_bytes, weight_vec.as_deref());
+    expected_remaining_from_buckets(&buckets, total)
+}
+
+// Small dependency-free splitmix64 step, used only to turn a u64 seed into a
+// reproducible stream of uniform draws for weighted reservoir sampling.
+#[inline]
+fn splitmix64_next(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E3779B97F4A7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+// Uniform draw in the open interval (0,1), needed so u^(1/w) below never hits
+// the undefined u=0 / u=1 edges.
+#[inline]
+fn next_unit_open(state: &mut u64) -> f64 {
+    // 52 bits, so that `top52 + 0.5` is exact in an f64 and the result stays below 1.
+    let top52 = splitmix64_next(state) >> 12;
+    (top52 as f64 + 0.5) / (1u64 << 52) as f64
+}
+
+// Candidate kept by algorithm A-Res, ordered so a `BinaryHeap<HeapItem>` behaves as a
+// min-heap on `key` (i.e. `peek`/`pop` surface the smallest key, the one to evict).
+struct HeapItem {
+    key: f64,
+    index: u32,
+}
+
+impl PartialEq for HeapItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+impl Eq for HeapItem {}
+impl PartialOrd for HeapItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for HeapItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key.partial_cmp(&self.key).unwrap_or(Ordering::Equal)
+    }
+}
+
+// Weighted reservoir sampling (algorithm A-Res): draws a representative size-`k`
+// subset of secret indices in a single streaming pass, so large dictionaries can be
+// scored approximately instead of against every candidate. For each secret `i` with
+// weight `wᵢ`, draw `uᵢ` from the seeded RNG and compute key `kᵢ = uᵢ^(1/wᵢ)`; keep the
+// `k` largest keys via a size-`k` min-heap, evicting the smallest when a larger key
+// arrives. The `seed` makes results reproducible across runs and machines. Kept as a
+// plain helper (no JS types) so the sampling/eviction logic is directly testable.
+fn sample_indices(weights: &[f64], k: usize, seed: u64) -> Vec<u32> {
+    let n = weights.len();
+    let k = k.min(n);
+    let mut state = seed;
+    let mut heap: BinaryHeap<HeapItem> = BinaryHeap::with_capacity(k);
+
+    for (i, &w) in weights.iter().enumerate() {
+        let u = next_unit_open(&mut state);
+        let key = u.powf(1.0 / w);
+        if heap.len() < k {
+            heap.push(HeapItem { key, index: i as u32 });
+        } else if heap.peek().is_some_and(|top| key > top.key) {
+            heap.pop();
+            heap.push(HeapItem { key, index: i as u32 });
+        }
+    }
+
+    let mut indices: Vec<u32> = heap.into_iter().map(|item| item.index).collect();
+    indices.sort_unstable();
+    indices
+}
+
+#[wasm_bindgen]
+pub fn sample_secrets(weights: js_sys::Float64Array, k: u32, seed: u64) -> js_sys::Uint32Array {
+    let indices = sample_indices(&weights.to_vec(), k as usize, seed);
+    let arr = js_sys::Uint32Array::new_with_length(indices.len() as u32);
+    arr.copy_from(&indices);
+    arr
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,4 +432,123 @@ mod tests {
         let yellows = tr2[..5].iter().filter(|&&t| t==1).count();
         assert!(yellows <= 3);
     }
+
+    #[test]
+    fn entropy_prefers_splitting_guess() {
+        let secrets: Vec<Vec<u8>> = ["abcde", "abcdf", "zzzzz"]
+            .iter()
+            .map(|s| s.as_bytes().to_vec())
+            .collect();
+        // "abcde" splits the three secrets into three buckets (itself vs "zzzzz" vs "abcdf"
+        // all land differently), so it should carry nonzero entropy.
+        let (h_split, is_possible) = guess_entropy(b"abcde", &secrets, None);
+        assert!(h_split > 0.0);
+        assert!(is_possible);
+
+        // A guess sharing no letters with any secret collapses everything into one
+        // all-gray bucket: zero entropy, and it isn't itself a candidate secret.
+        let (h_uniform, is_possible2) = guess_entropy(b"qqqqq", &secrets, None);
+        assert_eq!(h_uniform, 0.0);
+        assert!(!is_possible2);
+    }
+
+    #[test]
+    fn weighted_entropy_favors_heavy_secret() {
+        let secrets: Vec<Vec<u8>> = ["abcde", "abcdf", "zzzzz"]
+            .iter()
+            .map(|s| s.as_bytes().to_vec())
+            .collect();
+        // With near-all weight on one secret, the distribution is almost certain,
+        // so weighted entropy should come in well below the uniform case.
+        let weights = [100.0, 0.01, 0.01];
+        let (h_uniform, _) = guess_entropy(b"abcde", &secrets, None);
+        let (h_weighted, _) = guess_entropy(b"abcde", &secrets, Some(&weights));
+        assert!(h_weighted < h_uniform);
+    }
+
+    #[test]
+    fn expected_remaining_is_one_when_guess_fully_determines_secret() {
+        let secrets: Vec<Vec<u8>> = ["abcde", "zzzzz"]
+            .iter()
+            .map(|s| s.as_bytes().to_vec())
+            .collect();
+        // "abcde" splits the two secrets into two singleton buckets, so after
+        // guessing, exactly one candidate remains either way: expected mass = 1.
+        let (buckets, total, _) = bucket_weights(b"abcde", &secrets, None);
+        assert_eq!(expected_remaining_from_buckets(&buckets, total), 1.0);
+    }
+
+    #[test]
+    fn unit_draws_are_reproducible_for_a_fixed_seed() {
+        let weights = vec![1.0; 50];
+        let n = weights.len();
+        let mut state_a = 42u64;
+        let mut state_b = 42u64;
+        let draws_a: Vec<f64> = (0..n).map(|_| next_unit_open(&mut state_a)).collect();
+        let draws_b: Vec<f64> = (0..n).map(|_| next_unit_open(&mut state_b)).collect();
+        assert_eq!(draws_a, draws_b);
+        assert!(draws_a.iter().all(|&u| u > 0.0 && u < 1.0));
+    }
+
+    #[test]
+    fn sample_indices_is_reproducible_and_favors_heavy_weight() {
+        let weights = vec![1.0, 1.0, 1000.0, 1.0, 1.0];
+        // A weight that dwarfs the rest pushes its key k = u^(1/w) close to 1: with
+        // seed 7 it is about 0.9999, while the weight-1 items keep their raw draws
+        // (all below 0.6), so a single-slot reservoir keeps index 2.
+        let picked = sample_indices(&weights, 1, 7);
+        assert_eq!(picked, vec![2]);
+
+        let picked_again = sample_indices(&weights, 1, 7);
+        assert_eq!(picked, picked_again, "same seed must reproduce the same sample");
+
+        // Requesting more slots than secrets should just return every index.
+        let all = sample_indices(&weights, 10, 7);
+        assert_eq!(all, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn heap_item_min_heap_evicts_smallest_key() {
+        let mut heap: BinaryHeap<HeapItem> = BinaryHeap::new();
+        heap.push(HeapItem { key: 0.5, index: 0 });
+        heap.push(HeapItem { key: 0.1, index: 1 });
+        heap.push(HeapItem { key: 0.9, index: 2 });
+        // peek() on our reversed Ord should surface the smallest key (the one to evict).
+        assert_eq!(heap.peek().unwrap().index, 1);
+    }
+
+    #[test]
+    fn pattern_matrix_row_packing_matches_per_row_codes() {
+        let guesses: Vec<Vec<u8>> = ["abcde", "zzzzz"]
+            .iter()
+            .map(|s| s.as_bytes().to_vec())
+            .collect();
+        let secrets: Vec<Vec<u8>> = ["abcde", "abcdf", "zzzzz"]
+            .iter()
+            .map(|s| s.as_bytes().to_vec())
+            .collect();
+        let stride = secrets.len();
+        let flat = pack_pattern_matrix(&guesses, &secrets);
+
+        assert_eq!(flat.len(), guesses.len() * stride);
+        // Row 0 (guess "abcde") should score a perfect match against secret 0.
+        assert_eq!(flat[0], feedback_code("abcde", "abcde"));
+        // Row 1 (guess "zzzzz") should score a perfect match against secret 2.
+        assert_eq!(flat[stride + 2], feedback_code("zzzzz", "zzzzz"));
+    }
+
+    #[test]
+    fn base3_code_u64_matches_u16_for_short_lengths() {
+        let trits = [2u8, 0, 1, 2, 0];
+        assert_eq!(base3_code_u64(&trits), base3_code_u16(&trits) as u64);
+    }
+
+    #[test]
+    fn base3_code_u64_handles_lengths_past_ten() {
+        // 30 trits of all-green (2) exercises the wide u128 accumulator path.
+        let trits = [2u8; 30];
+        let code = base3_code_u64(&trits);
+        let expected: u128 = (0..30).map(|i| 2u128 * 3u128.pow(i)).sum();
+        assert_eq!(code as u128, expected);
+    }
 }